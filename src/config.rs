@@ -29,6 +29,7 @@ use std::{
 
 #[cfg(test)]
 mod tests;
+pub mod tsconfig;
 
 #[derive(Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -684,6 +685,7 @@ impl Merge for TransformConfig {
         self.optimizer.merge(&from.optimizer);
         self.const_modules.merge(&from.const_modules);
         self.react.merge(&from.react);
+        self.legacy_decorator.merge(&from.legacy_decorator);
     }
 }
 
@@ -0,0 +1,588 @@
+//! `tsconfig.json`
+
+use super::{JscConfig, TransformConfig};
+use crate::{
+    error::Error,
+    resolver::{self, ModuleResolution},
+};
+use ecmascript::{
+    parser::{EsConfig, JscTarget, Syntax, TsConfig},
+    transforms::react,
+};
+use path_clean::PathClean;
+use regex::Regex;
+use serde::Deserialize;
+use std::{
+    env,
+    ffi::OsStr,
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Directories never entered by wildcards unless a pattern names them, at
+/// any depth. They are also excluded if `exclude` is not specified.
+const PACKAGE_DIRS: &[&str] = &["node_modules", "bower_components", "jspm_packages"];
+
+/// A `tsconfig.json` file, merged with the files it `extends`.
+///
+/// Paths are resolved against the directory of the file which declared them.
+#[derive(Debug, Default, Clone)]
+pub struct TsConfigFile {
+    /// Directory containing the `tsconfig.json`.
+    pub dir: PathBuf,
+
+    pub compiler_options: CompilerOptions,
+
+    pub files: Option<Vec<PathBuf>>,
+
+    pub include: Option<Vec<PathBuf>>,
+
+    pub exclude: Option<Vec<PathBuf>>,
+}
+
+/// Options of `compilerOptions` used by swc. Other options are ignored.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompilerOptions {
+    #[serde(default)]
+    pub target: Option<String>,
+
+    #[serde(default)]
+    pub module_resolution: Option<ModuleResolution>,
+
+    #[serde(default)]
+    pub base_url: Option<PathBuf>,
+
+    #[serde(
+        default,
+        deserialize_with = "resolver::ordered_paths::deserialize_some"
+    )]
+    pub paths: Option<resolver::Paths>,
+
+    /// Directory of the file which declared `paths`.
+    #[serde(skip)]
+    pub paths_base: Option<PathBuf>,
+
+    #[serde(default)]
+    pub resolve_json_module: Option<bool>,
+
+    #[serde(default)]
+    pub allow_js: Option<bool>,
+
+    #[serde(default)]
+    pub experimental_decorators: Option<bool>,
+
+    #[serde(default)]
+    pub out_dir: Option<PathBuf>,
+
+    #[serde(default)]
+    pub jsx: Option<String>,
+
+    #[serde(default)]
+    pub jsx_factory: Option<String>,
+
+    #[serde(default)]
+    pub jsx_fragment_factory: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTsConfig {
+    #[serde(default)]
+    extends: Option<String>,
+    #[serde(default)]
+    compiler_options: CompilerOptions,
+    #[serde(default)]
+    files: Option<Vec<PathBuf>>,
+    #[serde(default)]
+    include: Option<Vec<PathBuf>>,
+    #[serde(default)]
+    exclude: Option<Vec<PathBuf>>,
+}
+
+impl TsConfigFile {
+    /// Loads `path`, following `extends`.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        load(path, &mut vec![])
+    }
+
+    /// Options of `compilerOptions` related to module resolution.
+    pub fn resolver_config(&self) -> resolver::Config {
+        let opts = &self.compiler_options;
+
+        resolver::Config {
+            module_resolution: opts.module_resolution.unwrap_or_default(),
+            base_url: opts.base_url.clone(),
+            paths: opts.paths.clone().unwrap_or_default(),
+            paths_base: opts.paths_base.clone(),
+            resolve_json_module: opts.resolve_json_module.unwrap_or(false),
+            allow_js: opts.allow_js.unwrap_or(false),
+        }
+    }
+
+    /// Options of `compilerOptions` used to compile `path`, which can be
+    /// merged into the [JscConfig] of `.swcrc`.
+    ///
+    /// Parsed JSX is always transformed, so `jsx: preserve` and
+    /// `react-native` are an error if `path` is parsed with JSX.
+    pub fn jsc_config(&self, path: &Path) -> Result<JscConfig, Error> {
+        let syntax = self.syntax(path);
+        let react = match self.react_options()? {
+            Some(react) => react,
+            None if !syntax.jsx() => Default::default(),
+            None => {
+                return Err(Error::UnsupportedJsx {
+                    jsx: self.compiler_options.jsx.clone().unwrap_or_default(),
+                })
+            }
+        };
+
+        Ok(JscConfig {
+            syntax: Some(syntax),
+            transform: Some(TransformConfig {
+                react,
+                legacy_decorator: self.compiler_options.experimental_decorators == Some(true),
+                ..Default::default()
+            }),
+            target: self.target()?,
+            ..Default::default()
+        })
+    }
+
+    /// Syntax used to parse `path`.
+    pub fn syntax(&self, path: &Path) -> Syntax {
+        let decorators = self.compiler_options.experimental_decorators == Some(true);
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("js") | Some("jsx") => Syntax::Es(EsConfig {
+                jsx: true,
+                decorators,
+                dynamic_import: true,
+                ..Default::default()
+            }),
+            ext => Syntax::Typescript(TsConfig {
+                tsx: ext == Some("tsx"),
+                decorators,
+                dynamic_import: true,
+            }),
+        }
+    }
+
+    /// Options of the jsx transform, or `None` if `jsx` is `preserve` or
+    /// `react-native`.
+    ///
+    /// The react transform only implements the classic runtime. The automatic
+    /// runtime (`react-jsx` and `react-jsxdev`) is an error, because classic
+    /// output would call a `React` global those projects never import.
+    pub fn react_options(&self) -> Result<Option<react::Options>, Error> {
+        let opts = &self.compiler_options;
+
+        if let Some(ref jsx) = opts.jsx {
+            match &*jsx.to_ascii_lowercase() {
+                "preserve" | "react-native" => return Ok(None),
+                "react" => {}
+                _ => return Err(Error::UnsupportedJsx { jsx: jsx.clone() }),
+            }
+        }
+
+        let mut options = react::Options::default();
+        if let Some(ref factory) = opts.jsx_factory {
+            options.pragma = factory.clone();
+        }
+        if let Some(ref factory) = opts.jsx_fragment_factory {
+            options.pragma_frag = factory.clone();
+        }
+        Ok(Some(options))
+    }
+
+    /// Target of `compilerOptions`.
+    ///
+    /// Targets newer than the ones swc supports are clamped to
+    /// [JscTarget::Es2019]. Unknown targets are an error.
+    pub fn target(&self) -> Result<JscTarget, Error> {
+        let target = match self.compiler_options.target {
+            Some(ref target) => target,
+            None => return Ok(JscTarget::Es3),
+        };
+
+        Ok(match &*target.to_ascii_lowercase() {
+            "es3" => JscTarget::Es3,
+            "es5" => JscTarget::Es5,
+            "es6" | "es2015" => JscTarget::Es2015,
+            "es2016" => JscTarget::Es2016,
+            "es2017" => JscTarget::Es2017,
+            "es2018" => JscTarget::Es2018,
+            "es2019" | "es2020" | "es2021" | "es2022" | "es2023" | "es2024" | "esnext" => {
+                JscTarget::Es2019
+            }
+            _ => {
+                return Err(Error::UnknownTarget {
+                    target: target.clone(),
+                })
+            }
+        })
+    }
+
+    /// Lists the files of the project, using `files`, `include` and
+    /// `exclude`.
+    pub fn source_files(&self) -> Result<Vec<PathBuf>, Error> {
+        let mut extensions = vec!["ts", "tsx"];
+        if self.compiler_options.allow_js == Some(true) {
+            extensions.extend(&["js", "jsx"]);
+        }
+
+        let mut exclude = vec![];
+        match self.exclude {
+            Some(ref patterns) => {
+                for pattern in patterns {
+                    exclude.push(glob_to_regex(pattern, true)?);
+                }
+            }
+            None => {
+                for dir in PACKAGE_DIRS {
+                    exclude.push(glob_to_regex(&self.dir.join(dir), true)?);
+                }
+                if let Some(ref out_dir) = self.compiler_options.out_dir {
+                    exclude.push(glob_to_regex(out_dir, true)?);
+                }
+            }
+        }
+
+        let mut files: Vec<PathBuf> = self.files.clone().unwrap_or_default();
+
+        let include = match self.include {
+            Some(ref include) => include.clone(),
+            // `files` disables the default pattern.
+            None if self.files.is_some() => vec![],
+            None => vec![self.dir.join("**/*")],
+        };
+
+        for pattern in include {
+            let pattern = if has_wildcard(&pattern) || pattern.extension().is_some() {
+                pattern
+            } else {
+                // A directory
+                pattern.join("**/*")
+            };
+
+            let re = glob_to_regex(&pattern, false)?;
+            let root = literal_prefix(&pattern);
+
+            // Package and hidden directories are only entered if the part of
+            // the pattern below `root` names them.
+            let named: Vec<String> = pattern
+                .strip_prefix(&root)
+                .unwrap_or(&pattern)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .filter(|c| !has_wildcard(Path::new(c)))
+                .collect();
+            let skip = |name: &str| {
+                (PACKAGE_DIRS.contains(&name) || name.starts_with('.'))
+                    && !named.iter().any(|c| c == name)
+            };
+
+            walk(&root, &exclude, &skip, &mut |path| {
+                let name = path.to_string_lossy().replace('\\', "/");
+                let has_ext = path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(|ext| extensions.contains(&ext))
+                    .unwrap_or(false);
+                if has_ext && re.is_match(&name) {
+                    files.push(path.to_path_buf());
+                }
+            });
+        }
+
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+}
+
+fn load(path: &Path, stack: &mut Vec<PathBuf>) -> Result<TsConfigFile, Error> {
+    // `tsconfig.json` would otherwise have an empty directory, which cannot be
+    // walked.
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir()
+            .map_err(|err| Error::FailedToReadConfigFile { err })?
+            .join(path)
+    }
+    .clean();
+    if stack.contains(&path) {
+        return Err(Error::CircularTsConfig {
+            path: path.to_string_lossy().into(),
+        });
+    }
+
+    let mut src = String::new();
+    File::open(&path)
+        .and_then(|mut f| f.read_to_string(&mut src))
+        .map_err(|err| Error::FailedToReadConfigFile { err })?;
+    let raw: RawTsConfig = serde_json::from_str(&strip_json_comments(&src))
+        .map_err(|err| Error::FailedToParseConfigFile { err })?;
+
+    let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let relative = |p: PathBuf| dir.join(p).clean();
+
+    let mut compiler_options = raw.compiler_options;
+    compiler_options.base_url = compiler_options.base_url.map(relative);
+    compiler_options.out_dir = compiler_options.out_dir.map(relative);
+    if compiler_options.paths.is_some() {
+        compiler_options.paths_base = Some(dir.clone());
+    }
+    let mut config = TsConfigFile {
+        dir: dir.clone(),
+        compiler_options,
+        files: raw.files.map(|v| v.into_iter().map(relative).collect()),
+        include: raw.include.map(|v| v.into_iter().map(relative).collect()),
+        exclude: raw.exclude.map(|v| v.into_iter().map(relative).collect()),
+    };
+
+    if let Some(extends) = raw.extends {
+        stack.push(path.clone());
+        let parent = load(&resolve_extends(&dir, &extends), stack)?;
+        stack.pop();
+
+        config.inherit(parent);
+    }
+
+    Ok(config)
+}
+
+impl TsConfigFile {
+    /// Fills options not specified by `self` from `parent`.
+    fn inherit(&mut self, parent: TsConfigFile) {
+        let opts = &mut self.compiler_options;
+        let parent_opts = parent.compiler_options;
+
+        macro_rules! inherit {
+            ($($field:ident),*) => {
+                $(
+                    if opts.$field.is_none() {
+                        opts.$field = parent_opts.$field;
+                    }
+                )*
+            };
+        }
+        inherit!(
+            target,
+            module_resolution,
+            base_url,
+            paths,
+            paths_base,
+            resolve_json_module,
+            allow_js,
+            experimental_decorators,
+            out_dir,
+            jsx,
+            jsx_factory,
+            jsx_fragment_factory
+        );
+
+        if self.files.is_none() {
+            self.files = parent.files;
+        }
+        if self.include.is_none() {
+            self.include = parent.include;
+        }
+        if self.exclude.is_none() {
+            self.exclude = parent.exclude;
+        }
+    }
+}
+
+/// `extends` is either a path or a package in `node_modules`.
+fn resolve_extends(dir: &Path, extends: &str) -> PathBuf {
+    if resolver::is_relative(extends) {
+        let path = dir.join(extends).clean();
+        if path.is_file() {
+            return path;
+        }
+        return with_json_ext(path);
+    }
+
+    for dir in dir.ancestors() {
+        let path = dir.join("node_modules").join(extends);
+        if path.is_dir() {
+            return path.join("tsconfig.json");
+        }
+        if path.is_file() {
+            return path;
+        }
+        let path = with_json_ext(path);
+        if path.is_file() {
+            return path;
+        }
+    }
+
+    dir.join("node_modules").join(extends)
+}
+
+/// Appends `.json` unless `path` already has it. `tsconfig.base` becomes
+/// `tsconfig.base.json`.
+fn with_json_ext(path: PathBuf) -> PathBuf {
+    if path.extension() == Some(OsStr::new("json")) {
+        return path;
+    }
+
+    let mut s = path.into_os_string();
+    s.push(".json");
+    s.into()
+}
+
+/// `tsconfig.json` may contain comments and trailing commas.
+fn strip_json_comments(src: &str) -> String {
+    let mut buf = String::with_capacity(src.len());
+    let mut chars = src.chars().peekable();
+    let mut in_str = false;
+
+    while let Some(c) = chars.next() {
+        if in_str {
+            buf.push(c);
+            match c {
+                '\\' => buf.extend(chars.next()),
+                '"' => in_str = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_str = true;
+                buf.push(c);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        buf.push(c);
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            ']' | '}' => {
+                let trimmed = buf.trim_end().len();
+                if buf[..trimmed].ends_with(',') {
+                    buf.truncate(trimmed - 1);
+                }
+                buf.push(c);
+            }
+            _ => buf.push(c),
+        }
+    }
+
+    buf
+}
+
+fn has_wildcard(path: &Path) -> bool {
+    let s = path.to_string_lossy();
+    s.contains('*') || s.contains('?')
+}
+
+/// Longest leading directory of `pattern` without wildcards.
+fn literal_prefix(pattern: &Path) -> PathBuf {
+    let mut buf = PathBuf::new();
+    for component in pattern.components() {
+        if has_wildcard(Path::new(&component)) {
+            break;
+        }
+        buf.push(component);
+    }
+    buf
+}
+
+/// Converts a glob pattern of tsconfig to a regex.
+///
+/// `prefix` makes the regex match everything inside matched directories,
+/// which is how `exclude` works. Otherwise, like tsc, wildcards do not match
+/// names starting with `.`.
+fn glob_to_regex(pattern: &Path, prefix: bool) -> Result<Regex, Error> {
+    let pattern = pattern.to_string_lossy().replace('\\', "/");
+    let mut re = String::from("^");
+
+    let allow_hidden = prefix;
+    let mut rest = &*pattern;
+    let mut component_start = true;
+    while !rest.is_empty() {
+        if rest.starts_with("**/") {
+            re.push_str(if allow_hidden {
+                "(?:[^/]*/)*"
+            } else {
+                "(?:[^/.][^/]*/)*"
+            });
+            rest = &rest[3..];
+            component_start = true;
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap();
+        match c {
+            '*' if component_start && !allow_hidden => re.push_str("(?:[^/.][^/]*)?"),
+            '*' => re.push_str("[^/]*"),
+            '?' if component_start && !allow_hidden => re.push_str("[^/.]"),
+            '?' => re.push_str("[^/]"),
+            _ => re.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+        rest = &rest[c.len_utf8()..];
+        component_start = c == '/';
+    }
+
+    if prefix {
+        re.push_str("(?:/.*)?");
+    }
+    re.push('$');
+
+    Regex::new(&re).map_err(|err| Error::InvalidRegex { regex: re, err })
+}
+
+/// Calls `op` with every file in `dir`, skipping paths matched by `exclude`
+/// and directories whose name `skip` returns true for. Unreadable directories
+/// are ignored, like tsc does.
+fn walk(dir: &Path, exclude: &[Regex], skip: &dyn Fn(&str) -> bool, op: &mut dyn FnMut(&Path)) {
+    if dir.is_file() {
+        op(dir);
+        return;
+    }
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = path.to_string_lossy().replace('\\', "/");
+        if exclude.iter().any(|re| re.is_match(&name)) {
+            continue;
+        }
+
+        match entry.file_type() {
+            Ok(ty) if ty.is_dir() => {
+                if entry.file_name().to_str().map_or(false, skip) {
+                    continue;
+                }
+                walk(&path, exclude, skip, op)
+            }
+            Ok(ty) if ty.is_file() => op(&path),
+            _ => {}
+        }
+    }
+}
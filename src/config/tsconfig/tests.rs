@@ -0,0 +1,247 @@
+use super::TsConfigFile;
+use crate::{
+    config::{JscConfig, Merge},
+    error::Error,
+    resolver::ModuleResolution,
+};
+use ecmascript::parser::{JscTarget, Syntax};
+use std::path::{Path, PathBuf};
+
+fn fixture(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("tsconfig")
+        .join(path)
+}
+
+fn load(path: &str) -> TsConfigFile {
+    TsConfigFile::load(&fixture(path)).expect("failed to load tsconfig")
+}
+
+#[test]
+fn extends() {
+    let config = load("project/tsconfig.json");
+
+    assert_eq!(config.target().unwrap(), JscTarget::Es2019);
+
+    let resolver = config.resolver_config();
+    assert_eq!(resolver.module_resolution, ModuleResolution::Node);
+    assert_eq!(resolver.base_url, Some(fixture("")));
+    assert_eq!(
+        resolver.paths,
+        vec![(String::from("@lib/*"), vec![String::from("lib/*")])]
+    );
+    // `paths` is declared by the base config.
+    assert_eq!(resolver.paths_base, Some(fixture("")));
+    assert!(resolver.allow_js);
+    assert!(!resolver.resolve_json_module);
+
+    match config.syntax(Path::new("view.tsx")) {
+        Syntax::Typescript(c) => assert!(c.tsx && c.decorators),
+        _ => unreachable!(),
+    }
+    match config.syntax(Path::new("legacy.js")) {
+        Syntax::Es(c) => assert!(c.jsx && c.decorators),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn include_exclude() {
+    let config = load("project/tsconfig.json");
+
+    assert_eq!(
+        config.source_files().unwrap(),
+        vec![
+            fixture("project/src/index.ts"),
+            fixture("project/src/legacy.js"),
+            fixture("project/src/view.tsx"),
+            fixture("project/types/global.d.ts"),
+        ]
+    );
+}
+
+#[test]
+fn default_include() {
+    let config = load("default/tsconfig.json");
+
+    assert_eq!(
+        config.resolver_config().module_resolution,
+        ModuleResolution::Classic
+    );
+    assert_eq!(config.target().unwrap(), JscTarget::Es3);
+    assert_eq!(
+        config.source_files().unwrap(),
+        vec![fixture("default/a.ts")]
+    );
+}
+
+#[test]
+fn files() {
+    let config = load("files/tsconfig.json");
+
+    assert_eq!(
+        config.source_files().unwrap(),
+        vec![fixture("files/main.ts")]
+    );
+}
+
+#[test]
+fn circular_extends() {
+    match TsConfigFile::load(&fixture("cycle/a.json")) {
+        Err(Error::CircularTsConfig { .. }) => {}
+        res => panic!("expected an error, got {:?}", res.map(|_| ())),
+    }
+}
+
+#[test]
+fn jsx_factory() {
+    let options = load("jsx/tsconfig.json")
+        .react_options()
+        .unwrap()
+        .expect("jsx should be transformed");
+    assert_eq!(options.pragma, "h");
+    assert_eq!(options.pragma_frag, "Fragment");
+
+    assert!(load("jsx/preserve.json").react_options().unwrap().is_none());
+
+    let options = load("files/tsconfig.json")
+        .react_options()
+        .unwrap()
+        .unwrap();
+    assert_eq!(options.pragma, "React.createElement");
+}
+
+#[test]
+fn jsx_automatic_runtime() {
+    for path in &["jsx/react-jsx.json", "jsx/react-jsxdev.json"] {
+        match load(path).react_options() {
+            Err(Error::UnsupportedJsx { .. }) => {}
+            res => panic!("expected an error, got {:?}", res),
+        }
+    }
+}
+
+#[test]
+fn package_dirs() {
+    // Package directories are skipped at any depth, even with `exclude`.
+    assert_eq!(
+        load("packages/tsconfig.json").source_files().unwrap(),
+        vec![
+            fixture("packages/index.ts"),
+            fixture("packages/packages/a/index.ts"),
+        ]
+    );
+
+    // ... unless a pattern names them.
+    assert_eq!(
+        load("packages/named.json").source_files().unwrap(),
+        vec![fixture("packages/packages/a/node_modules/y/index.d.ts")]
+    );
+}
+
+#[test]
+fn extends_package() {
+    assert_eq!(
+        load("extends/app/tsconfig.json").target().unwrap(),
+        JscTarget::Es2016
+    );
+    // A package directory uses its `tsconfig.json`.
+    assert_eq!(
+        load("extends/app/dir.json").target().unwrap(),
+        JscTarget::Es5
+    );
+}
+
+#[test]
+fn paths_without_base_url() {
+    let resolver = load("nobase/sub/tsconfig.json").resolver_config();
+
+    assert_eq!(resolver.base_url, None);
+    // Relative to the config declaring `paths`, not the one extending it.
+    assert_eq!(resolver.paths_base, Some(fixture("nobase")));
+}
+
+#[test]
+fn unsupported_module_resolution() {
+    match TsConfigFile::load(&fixture("bundler/tsconfig.json")) {
+        Err(Error::FailedToParseConfigFile { .. }) => {}
+        res => panic!("expected an error, got {:?}", res.map(|_| ())),
+    }
+}
+
+#[test]
+fn relative_path() {
+    // Tests run in the directory of the crate.
+    let config = TsConfigFile::load(Path::new("tests/tsconfig/default/tsconfig.json"))
+        .expect("failed to load tsconfig");
+
+    assert_eq!(config.dir, fixture("default"));
+    assert_eq!(
+        config.source_files().unwrap(),
+        vec![fixture("default/a.ts")]
+    );
+}
+
+#[test]
+fn target() {
+    assert_eq!(
+        load("target/newer.json").target().unwrap(),
+        JscTarget::Es2019
+    );
+
+    match load("target/typo.json").target() {
+        Err(Error::UnknownTarget { .. }) => {}
+        res => panic!("expected an error, got {:?}", res),
+    }
+}
+
+#[test]
+fn hidden_files() {
+    // Wildcards skip names starting with `.`.
+    assert_eq!(
+        load("hidden/tsconfig.json").source_files().unwrap(),
+        vec![fixture("hidden/src/index.ts")]
+    );
+
+    // ... unless a pattern names them.
+    assert_eq!(
+        load("hidden/named.json").source_files().unwrap(),
+        vec![fixture("hidden/src/.generated/gen.ts")]
+    );
+}
+
+#[test]
+fn jsc_config() {
+    let config = load("project/tsconfig.json");
+
+    let mut jsc = JscConfig::default();
+    jsc.merge(&config.jsc_config(Path::new("view.tsx")).unwrap());
+    assert_eq!(jsc.target, JscTarget::Es2019);
+    match jsc.syntax {
+        Some(Syntax::Typescript(c)) => assert!(c.tsx && c.decorators),
+        _ => unreachable!(),
+    }
+    let transform = jsc.transform.expect("transform should be set");
+    assert!(transform.legacy_decorator);
+    assert_eq!(transform.react.pragma, "React.createElement");
+
+    let jsc = load("jsx/tsconfig.json")
+        .jsc_config(Path::new("view.tsx"))
+        .unwrap();
+    let transform = jsc.transform.unwrap();
+    assert_eq!(transform.react.pragma, "h");
+    assert!(!transform.legacy_decorator);
+}
+
+#[test]
+fn jsc_config_preserve_jsx() {
+    let config = load("jsx/preserve.json");
+
+    // JSX can't be left as is once parsed.
+    match config.jsc_config(Path::new("view.tsx")) {
+        Err(Error::UnsupportedJsx { .. }) => {}
+        res => panic!("expected an error, got {:?}", res.map(|_| ())),
+    }
+    assert!(config.jsc_config(Path::new("index.ts")).is_ok());
+}
@@ -1,3 +1,4 @@
+use common::FileName;
 use failure::Fail;
 use lazy_static::lazy_static;
 use regex;
@@ -28,6 +29,18 @@ pub enum Error {
     #[fail(display = "sourcemap is not utf8: {}", err)]
     SourceMapNotUtf8 { err: FromUtf8Error },
 
+    #[fail(display = "failed to resolve module `{}` from {}", specifier, base)]
+    FailedToResolveModule { specifier: String, base: FileName },
+
+    #[fail(display = "circular `extends` in tsconfig: {}", path)]
+    CircularTsConfig { path: String },
+
+    #[fail(display = "unsupported jsx mode in tsconfig: {}", jsx)]
+    UnsupportedJsx { jsx: String },
+
+    #[fail(display = "unknown target in tsconfig: {}", target)]
+    UnknownTarget { target: String },
+
     #[fail(display = "invalid regexp: {}: {}", regex, err)]
     InvalidRegex { regex: String, err: regex::Error },
 
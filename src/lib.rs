@@ -8,6 +8,7 @@ pub use sourcemap;
 mod builder;
 pub mod config;
 pub mod error;
+pub mod resolver;
 
 pub use crate::builder::PassBuilder;
 use crate::{
@@ -0,0 +1,753 @@
+//! Module resolution.
+//!
+//! Maps import specifiers to the files they refer to.
+
+use crate::error::Error;
+use atoms::JsWord;
+use common::FileName;
+use ecmascript::ast::{Decl, Module, ModuleItem, Stmt, TsModuleDecl, TsModuleName};
+use hashbrown::HashMap;
+use path_clean::PathClean;
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::{
+    ffi::OsString,
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Extensions probed, in order, when a specifier does not name a file
+/// directly.
+const EXTENSIONS: &[&str] = &["ts", "tsx", "d.ts"];
+
+/// Probed if javascript files are allowed, once every candidate with
+/// [EXTENSIONS] has failed.
+const JS_EXTENSIONS: &[&str] = &["js", "jsx"];
+
+/// `moduleResolution` of `tsconfig.json`, which is case insensitive.
+///
+/// `node16`, `nodenext` and `bundler` are rejected instead of being treated
+/// as `node`, because they resolve differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ModuleResolution {
+    #[serde(rename = "node")]
+    Node,
+    #[serde(rename = "classic")]
+    Classic,
+}
+
+impl Default for ModuleResolution {
+    fn default() -> Self {
+        ModuleResolution::Node
+    }
+}
+
+impl<'de> Deserialize<'de> for ModuleResolution {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+
+        match &*s.to_ascii_lowercase() {
+            "node" | "node10" => Ok(ModuleResolution::Node),
+            "classic" => Ok(ModuleResolution::Classic),
+            "node16" | "nodenext" | "bundler" => Err(de::Error::custom(format_args!(
+                "unsupported moduleResolution `{}`",
+                s
+            ))),
+            _ => Err(de::Error::unknown_variant(
+                &s,
+                &["node", "node10", "classic"],
+            )),
+        }
+    }
+}
+
+/// Patterns of `paths` and their substitutions, in the declared order.
+pub type Paths = Vec<(String, Vec<String>)>;
+
+/// Resolution related options of `tsconfig.json`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct Config {
+    #[serde(default)]
+    pub module_resolution: ModuleResolution,
+
+    #[serde(default)]
+    pub base_url: Option<PathBuf>,
+
+    /// Substitutions are relative to `base_url`, or to `paths_base` if
+    /// `base_url` is not set. Ignored if neither is set.
+    ///
+    /// Patterns are kept in the declared order, which breaks ties between
+    /// patterns with prefixes of the same length.
+    #[serde(default, with = "ordered_paths")]
+    pub paths: Paths,
+
+    /// Directory of the `tsconfig.json` which declared `paths`.
+    #[serde(skip)]
+    pub paths_base: Option<PathBuf>,
+
+    /// Only used by the node resolution.
+    #[serde(default)]
+    pub resolve_json_module: bool,
+
+    #[serde(default)]
+    pub allow_js: bool,
+}
+
+impl Config {
+    pub fn build(self) -> Box<dyn Resolve> {
+        let inner: Box<dyn Resolve> = match self.module_resolution {
+            ModuleResolution::Node => box NodeResolver {
+                resolve_json_module: self.resolve_json_module,
+                allow_js: self.allow_js,
+            },
+            ModuleResolution::Classic => box ClassicResolver {
+                allow_js: self.allow_js,
+            },
+        };
+
+        match (self.base_url, self.paths_base) {
+            (Some(base_url), _) => box TsConfigResolver::new(inner, base_url, self.paths),
+            (None, Some(paths_base)) if !self.paths.is_empty() => {
+                box TsConfigResolver::without_base_url(inner, paths_base, self.paths)
+            }
+            _ => inner,
+        }
+    }
+}
+
+pub trait Resolve {
+    /// Resolves `specifier`, which is imported from the file named `base`.
+    fn resolve(&self, base: &FileName, specifier: &str) -> Result<FileName, Error>;
+}
+
+impl<R: ?Sized> Resolve for Box<R>
+where
+    R: Resolve,
+{
+    fn resolve(&self, base: &FileName, specifier: &str) -> Result<FileName, Error> {
+        (**self).resolve(base, specifier)
+    }
+}
+
+impl<R: ?Sized> Resolve for &R
+where
+    R: Resolve,
+{
+    fn resolve(&self, base: &FileName, specifier: &str) -> Result<FileName, Error> {
+        (**self).resolve(base, specifier)
+    }
+}
+
+/// Resolves modules using the algorithm of node.js, extended to understand
+/// typescript files.
+///
+///  - Relative and absolute specifiers are resolved against the importing file.
+///  - Other specifiers are looked up in `node_modules` of each ancestor
+///    directory, falling back to `node_modules/@types`.
+///  - A directory resolves to `types`, `typings` or `main` of its
+///    `package.json`, or to its `index` file.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NodeResolver {
+    /// Allows importing `.json` files by their full name.
+    pub resolve_json_module: bool,
+
+    /// Allows resolving to `.js` and `.jsx` files.
+    pub allow_js: bool,
+}
+
+/// Kinds of files probed by a resolution pass.
+///
+/// Like tsc, every typescript candidate is tried before any javascript file,
+/// so a `.js` file never shadows a `.ts` file or an `@types` package found
+/// later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Extensions {
+    /// `.ts`, `.tsx` and `.d.ts`, and `.json` if json modules are enabled.
+    TypeScript,
+    /// `.js` and `.jsx`.
+    JavaScript,
+}
+
+impl Extensions {
+    /// Passes to run, in order.
+    fn passes(allow_js: bool) -> &'static [Extensions] {
+        if allow_js {
+            &[Extensions::TypeScript, Extensions::JavaScript]
+        } else {
+            &[Extensions::TypeScript]
+        }
+    }
+}
+
+impl NodeResolver {
+    fn resolve_as_file(&self, path: &Path, exts: Extensions) -> Option<PathBuf> {
+        // `./foo.js` refers to `./foo.ts` once compiled.
+        let is_js = is_js_file(path);
+        let stem = if is_js {
+            path.with_extension("")
+        } else {
+            path.to_path_buf()
+        };
+
+        match exts {
+            Extensions::TypeScript => {
+                if is_ts_file(path) && path.is_file() {
+                    return Some(path.to_path_buf());
+                }
+
+                if self.resolve_json_module && has_ext(path, "json") && path.is_file() {
+                    return Some(path.to_path_buf());
+                }
+
+                probe(&stem, EXTENSIONS)
+            }
+            Extensions::JavaScript => {
+                if is_js && path.is_file() {
+                    return Some(path.to_path_buf());
+                }
+
+                probe(&stem, JS_EXTENSIONS)
+            }
+        }
+    }
+
+    fn resolve_as_directory(&self, dir: &Path, exts: Extensions) -> Option<PathBuf> {
+        // Javascript files are only reachable through `main`.
+        let fields: &[&str] = match exts {
+            Extensions::TypeScript => &["types", "typings", "main"],
+            Extensions::JavaScript => &["main"],
+        };
+
+        for entry in package_entries(&dir.join("package.json"), fields) {
+            let path = dir.join(entry).clean();
+            if let Some(path) = self.resolve_as_file(&path, exts) {
+                return Some(path);
+            }
+            if let Some(path) = self.resolve_as_file(&path.join("index"), exts) {
+                return Some(path);
+            }
+        }
+
+        self.resolve_as_file(&dir.join("index"), exts)
+    }
+
+    /// Resolves `path` as a file, and then as a directory.
+    fn resolve_path(&self, path: &Path, exts: Extensions) -> Option<PathBuf> {
+        if let Some(path) = self.resolve_as_file(path, exts) {
+            return Some(path);
+        }
+
+        if path.is_dir() {
+            return self.resolve_as_directory(path, exts);
+        }
+
+        None
+    }
+
+    fn resolve_node_modules(
+        &self,
+        base_dir: &Path,
+        name: &str,
+        exts: Extensions,
+    ) -> Option<PathBuf> {
+        for dir in base_dir.ancestors() {
+            let node_modules = dir.join("node_modules");
+            if !node_modules.is_dir() {
+                continue;
+            }
+
+            if let Some(path) = self.resolve_path(&node_modules.join(name), exts) {
+                return Some(path);
+            }
+
+            // `@types` only contains declarations.
+            if exts == Extensions::TypeScript {
+                let types = node_modules.join("@types").join(types_package_name(name));
+                if let Some(path) = self.resolve_path(&types, exts) {
+                    return Some(path);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl Resolve for NodeResolver {
+    fn resolve(&self, base: &FileName, specifier: &str) -> Result<FileName, Error> {
+        let base_dir = match base_dir(base) {
+            Some(dir) => dir,
+            None => return Err(failed(base, specifier)),
+        };
+
+        for &exts in Extensions::passes(self.allow_js) {
+            let resolved = if is_relative(specifier) {
+                self.resolve_path(&base_dir.join(specifier).clean(), exts)
+            } else {
+                self.resolve_node_modules(base_dir, specifier, exts)
+            };
+
+            if let Some(path) = resolved {
+                return Ok(FileName::Real(path));
+            }
+        }
+
+        Err(failed(base, specifier))
+    }
+}
+
+/// Resolves modules using the legacy algorithm of tsc.
+///
+///  - Relative specifiers are resolved against the importing file, without
+///    looking into directories.
+///  - Other specifiers are looked up in each ancestor directory, falling back
+///    to `node_modules/@types`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ClassicResolver {
+    /// Allows resolving to `.js` and `.jsx` files.
+    pub allow_js: bool,
+}
+
+impl Resolve for ClassicResolver {
+    fn resolve(&self, base: &FileName, specifier: &str) -> Result<FileName, Error> {
+        let base_dir = match base_dir(base) {
+            Some(dir) => dir,
+            None => return Err(failed(base, specifier)),
+        };
+        // Probes files like node does, but does not resolve json modules.
+        let files = NodeResolver::default();
+
+        for &exts in Extensions::passes(self.allow_js) {
+            if is_relative(specifier) {
+                let path = base_dir.join(specifier).clean();
+                if let Some(path) = files.resolve_as_file(&path, exts) {
+                    return Ok(FileName::Real(path));
+                }
+                continue;
+            }
+
+            for dir in base_dir.ancestors() {
+                if let Some(path) = files.resolve_as_file(&dir.join(specifier), exts) {
+                    return Ok(FileName::Real(path));
+                }
+            }
+
+            // Type declarations are looked up like node does.
+            if exts == Extensions::TypeScript {
+                for dir in base_dir.ancestors() {
+                    let types = dir
+                        .join("node_modules")
+                        .join("@types")
+                        .join(types_package_name(specifier));
+                    if let Some(path) = files.resolve_path(&types, exts) {
+                        return Ok(FileName::Real(path));
+                    }
+                }
+            }
+        }
+
+        Err(failed(base, specifier))
+    }
+}
+
+/// Applies `baseUrl` and `paths` of `tsconfig.json` to non-relative
+/// specifiers, and falls back to `inner` if they don't match any file.
+#[derive(Debug, Clone)]
+pub struct TsConfigResolver<R> {
+    inner: R,
+    base_url: Option<PathBuf>,
+    /// Directory substitutions of `paths` are relative to.
+    paths_base: PathBuf,
+    /// Patterns and their substitutions.
+    paths: Paths,
+}
+
+impl<R> TsConfigResolver<R>
+where
+    R: Resolve,
+{
+    pub fn new(inner: R, base_url: PathBuf, paths: Paths) -> Self {
+        TsConfigResolver {
+            inner,
+            paths_base: base_url.clone(),
+            base_url: Some(base_url),
+            paths,
+        }
+    }
+
+    /// Applies only `paths`, with substitutions relative to `paths_base`.
+    ///
+    /// This is how tsc handles `paths` without `baseUrl`, using the directory
+    /// of the `tsconfig.json` declaring them.
+    pub fn without_base_url(inner: R, paths_base: PathBuf, paths: Paths) -> Self {
+        TsConfigResolver {
+            inner,
+            base_url: None,
+            paths_base,
+            paths,
+        }
+    }
+
+    /// Resolves an absolute path using `inner`, treating an unresolved module
+    /// as a miss.
+    fn try_path(&self, base: &FileName, path: &Path) -> Result<Option<FileName>, Error> {
+        match self.inner.resolve(base, &path.to_string_lossy()) {
+            Ok(name) => Ok(Some(name)),
+            Err(Error::FailedToResolveModule { .. }) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Finds the pattern matching `specifier` with the longest prefix, like
+    /// tsc does.
+    fn best_match<'a>(&'a self, specifier: &'a str) -> Option<(&'a [String], &'a str)> {
+        longest_match(
+            self.paths
+                .iter()
+                .map(|&(ref pattern, ref substitutions)| (&**pattern, &**substitutions)),
+            specifier,
+        )
+    }
+}
+
+impl<R> Resolve for TsConfigResolver<R>
+where
+    R: Resolve,
+{
+    fn resolve(&self, base: &FileName, specifier: &str) -> Result<FileName, Error> {
+        if is_relative(specifier) {
+            return self.inner.resolve(base, specifier);
+        }
+
+        if let Some((substitutions, captured)) = self.best_match(specifier) {
+            for substitution in substitutions {
+                let path = self
+                    .paths_base
+                    .join(substitution.replacen('*', captured, 1))
+                    .clean();
+                if let Some(name) = self.try_path(base, &path)? {
+                    return Ok(name);
+                }
+            }
+        }
+
+        if let Some(ref base_url) = self.base_url {
+            if let Some(name) = self.try_path(base, &base_url.join(specifier).clean())? {
+                return Ok(name);
+            }
+        }
+
+        self.inner.resolve(base, specifier)
+    }
+}
+
+/// Matches `specifier` against a `paths` pattern, which contains at most one
+/// `*`.
+///
+/// Returns the length of the prefix and the text matched by `*`. An exact
+/// match is preferred over any wildcard.
+fn match_pattern<'a>(pattern: &str, specifier: &'a str) -> Option<(usize, &'a str)> {
+    match pattern.find('*') {
+        Some(idx) => {
+            let (prefix, suffix) = (&pattern[..idx], &pattern[idx + 1..]);
+            if specifier.len() >= prefix.len() + suffix.len()
+                && specifier.starts_with(prefix)
+                && specifier.ends_with(suffix)
+            {
+                Some((
+                    prefix.len(),
+                    &specifier[prefix.len()..specifier.len() - suffix.len()],
+                ))
+            } else {
+                None
+            }
+        }
+        None if pattern == specifier => Some((usize::MAX, "")),
+        None => None,
+    }
+}
+
+/// Finds the pattern matching `specifier` with the longest prefix, using
+/// [match_pattern]. The first pattern wins a tie.
+///
+/// Returns the value paired with the pattern and the text matched by `*`.
+fn longest_match<'a, 'p, T, I>(patterns: I, specifier: &'a str) -> Option<(T, &'a str)>
+where
+    I: IntoIterator<Item = (&'p str, T)>,
+{
+    let mut best: Option<(usize, T, &str)> = None;
+
+    for (pattern, value) in patterns {
+        let (prefix_len, captured) = match match_pattern(pattern, specifier) {
+            Some(v) => v,
+            None => continue,
+        };
+
+        match best {
+            Some((len, ..)) if len >= prefix_len => {}
+            _ => best = Some((prefix_len, value, captured)),
+        }
+    }
+
+    best.map(|(_, value, captured)| (value, captured))
+}
+
+/// Modules declared with `declare module "foo" { ... }` in global declaration
+/// files.
+///
+/// Declarations of the same module are merged, in the order they are added.
+#[derive(Debug, Default, Clone)]
+pub struct AmbientModules {
+    modules: HashMap<JsWord, Vec<TsModuleDecl>>,
+    /// Names containing `*`, like `*.css`.
+    patterns: Vec<JsWord>,
+}
+
+impl AmbientModules {
+    /// Registers the ambient modules declared in `module`.
+    ///
+    /// `declare module` in a file with imports or exports augments a module
+    /// instead of declaring one, so such files are ignored.
+    pub fn add(&mut self, module: &Module) {
+        let is_module = module.body.iter().any(|item| match *item {
+            ModuleItem::ModuleDecl(..) => true,
+            _ => false,
+        });
+        if is_module {
+            return;
+        }
+
+        for item in &module.body {
+            let decl = match *item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::TsModule(ref decl))) => decl,
+                _ => continue,
+            };
+
+            let name = match decl.id {
+                TsModuleName::Str(ref name) => &name.value,
+                TsModuleName::Ident(..) => continue,
+            };
+
+            if name.contains('*') && !self.modules.contains_key(name) {
+                self.patterns.push(name.clone());
+            }
+            self.modules
+                .entry(name.clone())
+                .or_default()
+                .push(decl.clone());
+        }
+    }
+
+    /// Returns all declarations of the module named `name`.
+    pub fn get(&self, name: &str) -> Option<&[TsModuleDecl]> {
+        self.modules.get(&JsWord::from(name)).map(|decls| &**decls)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.modules.contains_key(&JsWord::from(name))
+    }
+
+    /// Finds the pattern matching `specifier` with the longest prefix.
+    pub fn find_pattern(&self, specifier: &str) -> Option<&JsWord> {
+        longest_match(
+            self.patterns.iter().map(|pattern| (&**pattern, pattern)),
+            specifier,
+        )
+        .map(|(pattern, _)| pattern)
+    }
+}
+
+/// Resolves names of ambient modules before consulting `inner`, and
+/// wildcard patterns like `*.css` for specifiers `inner` cannot resolve.
+///
+/// An ambient module resolves to [FileName::Custom] containing its name or
+/// pattern, which can be passed to [AmbientModules::get].
+#[derive(Debug, Clone)]
+pub struct AmbientResolver<R> {
+    inner: R,
+    modules: AmbientModules,
+}
+
+impl<R> AmbientResolver<R>
+where
+    R: Resolve,
+{
+    pub fn new(inner: R, modules: AmbientModules) -> Self {
+        AmbientResolver { inner, modules }
+    }
+
+    pub fn modules(&self) -> &AmbientModules {
+        &self.modules
+    }
+}
+
+impl<R> Resolve for AmbientResolver<R>
+where
+    R: Resolve,
+{
+    fn resolve(&self, base: &FileName, specifier: &str) -> Result<FileName, Error> {
+        // Ambient module names cannot be relative.
+        if !is_relative(specifier) && self.modules.contains(specifier) {
+            return Ok(FileName::Custom(specifier.into()));
+        }
+
+        match self.inner.resolve(base, specifier) {
+            Err(Error::FailedToResolveModule { .. }) => {
+                match self.modules.find_pattern(specifier) {
+                    Some(pattern) => Ok(FileName::Custom(pattern.to_string())),
+                    None => Err(failed(base, specifier)),
+                }
+            }
+            res => res,
+        }
+    }
+}
+
+/// Entry points named by `fields` of a `package.json`, in order.
+///
+/// Like tsc, a file which cannot be read or parsed is treated as empty, and
+/// fields which are not strings are ignored.
+fn package_entries(path: &Path, fields: &[&str]) -> Vec<String> {
+    let pkg: serde_json::Value = match File::open(path)
+        .ok()
+        .and_then(|r| serde_json::from_reader(r).ok())
+    {
+        Some(pkg) => pkg,
+        None => return vec![],
+    };
+
+    fields
+        .iter()
+        .filter_map(|&field| pkg.get(field).and_then(|v| v.as_str()))
+        .map(String::from)
+        .collect()
+}
+
+/// Directory containing `base`, if it's a real file.
+fn base_dir(base: &FileName) -> Option<&Path> {
+    match *base {
+        FileName::Real(ref path) => path.parent(),
+        _ => None,
+    }
+}
+
+fn failed(base: &FileName, specifier: &str) -> Error {
+    Error::FailedToResolveModule {
+        specifier: specifier.into(),
+        base: base.clone(),
+    }
+}
+
+/// Returns true for `./foo`, `../foo` and `/foo`.
+pub(crate) fn is_relative(specifier: &str) -> bool {
+    specifier == "."
+        || specifier == ".."
+        || specifier.starts_with("./")
+        || specifier.starts_with("../")
+        || Path::new(specifier).is_absolute()
+}
+
+fn has_ext(path: &Path, ext: &str) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some(ext)
+}
+
+fn is_ts_file(path: &Path) -> bool {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("ts") | Some("tsx") => true,
+        _ => false,
+    }
+}
+
+fn is_js_file(path: &Path) -> bool {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("js") | Some("jsx") => true,
+        _ => false,
+    }
+}
+
+/// First file named `stem` with one of `exts` appended.
+fn probe(stem: &Path, exts: &[&str]) -> Option<PathBuf> {
+    exts.iter()
+        .map(|ext| with_ext(stem, ext))
+        .find(|path| path.is_file())
+}
+
+fn with_ext(path: &Path, ext: &str) -> PathBuf {
+    let mut s = OsString::from(path.as_os_str());
+    s.push(".");
+    s.push(ext);
+    PathBuf::from(s)
+}
+
+/// `@scope/name` is published as `@types/scope__name`.
+fn types_package_name(name: &str) -> String {
+    if name.starts_with('@') {
+        name[1..].replacen('/', "__", 1)
+    } else {
+        name.into()
+    }
+}
+
+/// (De)serializes [Paths] as a json object without losing the order of keys.
+pub(crate) mod ordered_paths {
+    use super::Paths;
+    use serde::{
+        de::{MapAccess, Visitor},
+        Deserializer, Serializer,
+    };
+    use std::fmt;
+
+    pub fn serialize<S>(paths: &Paths, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(paths.iter().map(|&(ref k, ref v)| (k, v)))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Paths, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(PathsVisitor)
+    }
+
+    /// For optional fields, which should also use `#[serde(default)]`.
+    pub fn deserialize_some<'de, D>(deserializer: D) -> Result<Option<Paths>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize(deserializer).map(Some)
+    }
+
+    struct PathsVisitor;
+
+    impl<'de> Visitor<'de> for PathsVisitor {
+        type Value = Paths;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a map of patterns to substitutions")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Paths, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut paths: Paths = Vec::with_capacity(map.size_hint().unwrap_or(0));
+            while let Some((pattern, substitutions)) = map.next_entry::<String, Vec<String>>()? {
+                // Like `JSON.parse`, a duplicated key keeps its first position
+                // and its last value.
+                match paths.iter_mut().find(|&&mut (ref p, _)| *p == pattern) {
+                    Some(entry) => entry.1 = substitutions,
+                    None => paths.push((pattern, substitutions)),
+                }
+            }
+            Ok(paths)
+        }
+    }
+}
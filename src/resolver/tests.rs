@@ -0,0 +1,418 @@
+use super::{
+    AmbientModules, AmbientResolver, ClassicResolver, Config, ModuleResolution, NodeResolver,
+    Resolve, TsConfigResolver,
+};
+use common::FileName;
+use ecmascript::{
+    ast::Module,
+    parser::{lexer::Lexer, Parser, Session, SourceFileInput, Syntax},
+};
+use std::path::PathBuf;
+
+fn fixture(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("resolver")
+        .join(path)
+}
+
+fn resolve(r: &dyn Resolve, base: &str, specifier: &str) -> PathBuf {
+    match r
+        .resolve(&FileName::Real(fixture(base)), specifier)
+        .expect("failed to resolve")
+    {
+        FileName::Real(path) => path,
+        name => panic!("resolved to a non-file: {}", name),
+    }
+}
+
+#[test]
+fn node_relative() {
+    let r = NodeResolver::default();
+    let base = "node/src/index.ts";
+
+    assert_eq!(resolve(&r, base, "./foo"), fixture("node/src/foo.ts"));
+    assert_eq!(resolve(&r, base, "./bar"), fixture("node/src/bar.d.ts"));
+    assert_eq!(resolve(&r, base, "./dir"), fixture("node/src/dir/index.ts"));
+    assert_eq!(
+        resolve(&r, base, "./js-ext.js"),
+        fixture("node/src/js-ext.ts")
+    );
+    assert_eq!(
+        resolve(&r, base, "../src/foo.ts"),
+        fixture("node/src/foo.ts")
+    );
+}
+
+#[test]
+fn node_modules() {
+    let r = NodeResolver::default();
+    let base = "node/src/dir/index.ts";
+
+    assert_eq!(
+        resolve(&r, base, "typed"),
+        fixture("node/node_modules/typed/lib/typed.d.ts")
+    );
+    assert_eq!(
+        resolve(&r, base, "main-only"),
+        fixture("node/node_modules/main-only/dist/index.d.ts")
+    );
+    assert_eq!(
+        resolve(&r, base, "untyped"),
+        fixture("node/node_modules/@types/untyped/index.d.ts")
+    );
+    assert_eq!(
+        resolve(&r, base, "@scope/pkg"),
+        fixture("node/node_modules/@types/scope__pkg/index.d.ts")
+    );
+}
+
+#[test]
+fn node_bad_package_json() {
+    let r = NodeResolver::default();
+    let base = "node/src/index.ts";
+
+    // Unusable package.json files are ignored instead of failing resolution.
+    assert_eq!(
+        resolve(&r, base, "broken"),
+        fixture("node/node_modules/broken/index.d.ts")
+    );
+    assert_eq!(
+        resolve(&r, base, "odd-main"),
+        fixture("node/node_modules/odd-main/odd.d.ts")
+    );
+}
+
+#[test]
+fn node_json() {
+    let base = "node/src/index.ts";
+    let r = NodeResolver {
+        resolve_json_module: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        resolve(&r, base, "./data.json"),
+        fixture("node/src/data.json")
+    );
+    // Json modules must be imported with the extension.
+    assert!(r.resolve(&FileName::Real(fixture(base)), "./data").is_err());
+    assert!(NodeResolver::default()
+        .resolve(&FileName::Real(fixture(base)), "./data.json")
+        .is_err());
+}
+
+#[test]
+fn node_allow_js() {
+    let base = "node/src/index.ts";
+    let r = NodeResolver {
+        allow_js: true,
+        ..Default::default()
+    };
+
+    assert_eq!(resolve(&r, base, "./legacy"), fixture("node/src/legacy.js"));
+    assert_eq!(
+        resolve(&r, base, "./legacy.js"),
+        fixture("node/src/legacy.js")
+    );
+    assert_eq!(
+        resolve(&r, base, "./component"),
+        fixture("node/src/component.jsx")
+    );
+    // Typescript files are preferred.
+    assert_eq!(
+        resolve(&r, base, "./js-ext.js"),
+        fixture("node/src/js-ext.ts")
+    );
+    assert!(NodeResolver::default()
+        .resolve(&FileName::Real(fixture(base)), "./legacy")
+        .is_err());
+}
+
+#[test]
+fn node_allow_js_second_pass() {
+    let base = "node/src/index.ts";
+    let r = NodeResolver {
+        allow_js: true,
+        ..Default::default()
+    };
+
+    // Javascript files are only tried once every typescript candidate failed,
+    // including `@types` and directory indexes.
+    assert_eq!(
+        resolve(&r, base, "jslib"),
+        fixture("node/node_modules/@types/jslib/index.d.ts")
+    );
+    assert_eq!(
+        resolve(&r, base, "./both"),
+        fixture("node/src/both/index.ts")
+    );
+    assert_eq!(
+        resolve(&r, base, "jsonly"),
+        fixture("node/node_modules/jsonly/lib/main.js")
+    );
+}
+
+#[test]
+fn node_unresolved() {
+    let base = FileName::Real(fixture("node/src/index.ts"));
+
+    let r = NodeResolver::default();
+
+    assert!(r.resolve(&base, "./missing").is_err());
+    assert!(r.resolve(&base, "missing").is_err());
+    assert!(r.resolve(&FileName::Anon, "./foo").is_err());
+}
+
+fn paths_resolver() -> TsConfigResolver<NodeResolver> {
+    TsConfigResolver::new(
+        NodeResolver::default(),
+        fixture("paths"),
+        vec![
+            ("@app/*".into(), vec!["src/app/*".into()]),
+            (
+                "@shared/*".into(),
+                vec!["missing/*".into(), "src/shared/*".into()],
+            ),
+            ("@shared/log".into(), vec!["src/app/utils".into()]),
+        ],
+    )
+}
+
+#[test]
+fn paths() {
+    let r = paths_resolver();
+    let base = "paths/src/index.ts";
+
+    assert_eq!(
+        resolve(&r, base, "@app/utils"),
+        fixture("paths/src/app/utils.ts")
+    );
+    // Substitutions are tried in order.
+    assert_eq!(
+        resolve(&r, base, "@shared/other"),
+        fixture("paths/src/shared/other.ts")
+    );
+    // Exact matches are preferred over wildcards.
+    assert_eq!(
+        resolve(&r, base, "@shared/log"),
+        fixture("paths/src/app/utils.ts")
+    );
+}
+
+#[test]
+fn paths_fallback() {
+    let r = paths_resolver();
+    let base = "paths/src/index.ts";
+
+    assert_eq!(
+        resolve(&r, base, "src/shared/log"),
+        fixture("paths/src/shared/log.ts")
+    );
+    assert_eq!(
+        resolve(&r, base, "pkg"),
+        fixture("paths/node_modules/pkg/index.d.ts")
+    );
+    assert_eq!(
+        resolve(&r, base, "./app/utils"),
+        fixture("paths/src/app/utils.ts")
+    );
+    assert!(r
+        .resolve(&FileName::Real(fixture(base)), "@app/missing")
+        .is_err());
+}
+
+#[test]
+fn paths_without_base_url() {
+    let r = TsConfigResolver::without_base_url(
+        NodeResolver::default(),
+        fixture("paths"),
+        vec![("@app/*".into(), vec!["src/app/*".into()])],
+    );
+    let base = "paths/src/index.ts";
+
+    assert_eq!(
+        resolve(&r, base, "@app/utils"),
+        fixture("paths/src/app/utils.ts")
+    );
+    assert_eq!(
+        resolve(&r, base, "pkg"),
+        fixture("paths/node_modules/pkg/index.d.ts")
+    );
+    // Without `baseUrl`, other specifiers are not resolved against the
+    // directory.
+    assert!(r
+        .resolve(&FileName::Real(fixture(base)), "src/shared/log")
+        .is_err());
+}
+
+#[test]
+fn paths_declared_order() {
+    let base = "paths/src/index.ts";
+    let build = |paths: &str| {
+        let json = format!(
+            r#"{{ "baseUrl": {:?}, "paths": {} }}"#,
+            fixture("paths"),
+            paths
+        );
+        serde_json::from_str::<Config>(&json)
+            .expect("failed to parse")
+            .build()
+    };
+
+    // Both patterns have the prefix `@x/`, so the one declared first wins.
+    let r = build(r#"{ "@x/*": ["src/shared/*"], "@x/*g": ["src/app/utils"] }"#);
+    assert_eq!(
+        resolve(&*r, base, "@x/log"),
+        fixture("paths/src/shared/log.ts")
+    );
+    let r = build(r#"{ "@x/*g": ["src/app/utils"], "@x/*": ["src/shared/*"] }"#);
+    assert_eq!(
+        resolve(&*r, base, "@x/log"),
+        fixture("paths/src/app/utils.ts")
+    );
+}
+
+#[test]
+fn classic() {
+    let r = ClassicResolver::default();
+    let base = "classic/src/nested/index.ts";
+
+    assert_eq!(
+        resolve(&r, base, "./sibling"),
+        fixture("classic/src/nested/sibling.ts")
+    );
+    assert_eq!(
+        resolve(&r, base, "shared"),
+        fixture("classic/src/shared.ts")
+    );
+    assert_eq!(
+        resolve(&r, base, "typed"),
+        fixture("classic/node_modules/@types/typed/index.d.ts")
+    );
+
+    let base = FileName::Real(fixture(base));
+    assert!(r.resolve(&base, "../dir").is_err());
+    assert!(r.resolve(&base, "nodepkg").is_err());
+    assert!(r.resolve(&base, "legacy").is_err());
+
+    let r = ClassicResolver { allow_js: true };
+    assert_eq!(
+        r.resolve(&base, "legacy").expect("failed to resolve"),
+        FileName::Real(fixture("classic/src/legacy.js"))
+    );
+    // A nearer javascript file does not shadow a typescript file.
+    assert_eq!(
+        r.resolve(&base, "farther").expect("failed to resolve"),
+        FileName::Real(fixture("classic/src/farther.ts"))
+    );
+}
+
+#[test]
+fn config_module_resolution() {
+    let config: Config =
+        serde_json::from_str(r#"{ "moduleResolution": "classic" }"#).expect("failed to parse");
+    let r = config.build();
+    let base = FileName::Real(fixture("classic/src/nested/index.ts"));
+
+    assert!(r.resolve(&base, "shared").is_ok());
+    assert!(r.resolve(&base, "nodepkg").is_err());
+    assert!(NodeResolver::default().resolve(&base, "nodepkg").is_ok());
+}
+
+#[test]
+fn config_module_resolution_values() {
+    let parse = |value: &str| {
+        serde_json::from_str::<Config>(&format!(r#"{{ "moduleResolution": {:?} }}"#, value))
+            .map(|config| config.module_resolution)
+    };
+
+    assert_eq!(parse("Classic").unwrap(), ModuleResolution::Classic);
+    assert_eq!(parse("NODE").unwrap(), ModuleResolution::Node);
+    assert_eq!(parse("node10").unwrap(), ModuleResolution::Node);
+    // Unsupported and unknown modes are errors, not `node`.
+    assert!(parse("bundler").is_err());
+    assert!(parse("NodeNext").is_err());
+    assert!(parse("nod").is_err());
+}
+
+fn ambient_modules(files: &[&str]) -> AmbientModules {
+    testing::run_test(false, |cm, handler| {
+        let mut modules = AmbientModules::default();
+
+        for file in files {
+            let fm = cm.load_file(&fixture(file)).expect("failed to load file");
+            let session = Session { handler };
+            let lexer = Lexer::new(
+                session,
+                Syntax::Typescript(Default::default()),
+                Default::default(),
+                SourceFileInput::from(&*fm),
+                None,
+            );
+            let module: Module =
+                Parser::new_from(session, lexer)
+                    .parse_module()
+                    .map_err(|mut e| {
+                        e.emit();
+                    })?;
+
+            modules.add(&module);
+        }
+
+        Ok(modules)
+    })
+    .unwrap()
+}
+
+#[test]
+fn ambient_modules_merge() {
+    let modules = ambient_modules(&["ambient/a.d.ts", "ambient/b.d.ts", "ambient/augment.d.ts"]);
+
+    assert_eq!(modules.get("foo").map(|decls| decls.len()), Some(2));
+    assert_eq!(modules.get("bar").map(|decls| decls.len()), Some(1));
+    assert!(!modules.contains("NotAModule"));
+    assert!(!modules.contains("augmented"));
+}
+
+#[test]
+fn ambient_resolver() {
+    let modules = ambient_modules(&["ambient/a.d.ts", "ambient/b.d.ts"]);
+    let r = AmbientResolver::new(NodeResolver::default(), modules);
+    let base = FileName::Real(fixture("ambient/src/index.ts"));
+
+    // Declared modules are preferred over the file system.
+    assert_eq!(
+        r.resolve(&base, "foo").expect("failed to resolve"),
+        FileName::Custom("foo".into())
+    );
+    assert_eq!(
+        resolve(&r, "ambient/src/index.ts", "./local"),
+        fixture("ambient/src/local.ts")
+    );
+    assert!(r.resolve(&base, "baz").is_err());
+}
+
+#[test]
+fn ambient_patterns() {
+    let modules = ambient_modules(&["ambient/a.d.ts", "ambient/patterns.d.ts"]);
+    assert_eq!(modules.get("*.css").map(|decls| decls.len()), Some(2));
+
+    let r = AmbientResolver::new(NodeResolver::default(), modules);
+    let base = FileName::Real(fixture("ambient/src/index.ts"));
+    let pattern = |specifier| r.resolve(&base, specifier).expect("failed to resolve");
+
+    assert_eq!(pattern("./app.css"), FileName::Custom("*.css".into()));
+    assert_eq!(pattern("config/db"), FileName::Custom("config/*".into()));
+    // The pattern with the longest prefix wins.
+    assert_eq!(
+        pattern("config/app/main"),
+        FileName::Custom("config/app/*".into())
+    );
+    // Patterns are only used for specifiers which are not files.
+    assert_eq!(
+        resolve(&r, "ambient/src/index.ts", "./real.css"),
+        fixture("ambient/src/real.css.ts")
+    );
+    assert!(r.resolve(&base, "./app.scss").is_err());
+}
@@ -0,0 +1,3 @@
+declare module "foo" {
+    export const a: number;
+}
@@ -0,0 +1,3 @@
+import "foo";
+
+declare module "augmented" {}
@@ -0,0 +1,7 @@
+declare module "foo" {
+    export const b: number;
+}
+
+declare module "bar" {}
+
+declare namespace NotAModule {}
@@ -0,0 +1,10 @@
+declare module "*.css" {
+    const classes: { [key: string]: string };
+    export default classes;
+}
+
+declare module "config/*" {}
+
+declare module "config/app/*" {}
+
+declare module "*.css" {}
@@ -0,0 +1 @@
+export const farther = 1;
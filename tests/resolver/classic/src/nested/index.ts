@@ -0,0 +1,2 @@
+import { sibling } from './sibling';
+import { shared } from 'shared';
@@ -0,0 +1 @@
+export const sibling = 1;
@@ -0,0 +1 @@
+export const both = 1;
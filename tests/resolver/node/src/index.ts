@@ -0,0 +1,4 @@
+import { foo } from './foo';
+import { bar } from './bar';
+import { dir } from './dir';
+import { js } from './js-ext.js';
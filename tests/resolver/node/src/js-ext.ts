@@ -0,0 +1 @@
+export const js = 1;
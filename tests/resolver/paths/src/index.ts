@@ -0,0 +1,2 @@
+import { utils } from '@app/utils';
+import { log } from '@shared/log';
@@ -0,0 +1 @@
+export const out = 1;
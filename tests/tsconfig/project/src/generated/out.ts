@@ -0,0 +1 @@
+export const generated = 1;
@@ -0,0 +1 @@
+declare const global: number;